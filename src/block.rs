@@ -0,0 +1,46 @@
+//! The shape [`crate::code_block::CodeBlock`], [`crate::diff::DiffBlock`],
+//! and [`crate::graph::GraphBlock`] all have in common: a block owns its raw
+//! source text and can render a sensible default view of it with no further
+//! input. [`FencedBlock`] is that common surface — a caller dispatching on a
+//! fenced block's language tag can hold any of the three behind `&dyn
+//! FencedBlock` rather than matching on the concrete type before it can ask
+//! for source or a rendering.
+//!
+//! It's deliberately thin: each type's richer, parameterized renders
+//! (`CodeBlock::render_with_diagnostics`, `GraphBlock::render(View::Source)`,
+//! ...) stay on the concrete type, since those need inputs a trait object
+//! can't carry.
+
+/// A fenced block that owns its raw source and can render a default view of
+/// it standalone.
+pub trait FencedBlock {
+    /// The raw, unrendered source text.
+    fn source(&self) -> &str;
+
+    /// Render this block's default view: [`CodeBlock`](crate::code_block::CodeBlock)
+    /// with dead-store dimming and no diagnostics, [`DiffBlock`](crate::diff::DiffBlock)'s
+    /// standard tinted layout, and [`GraphBlock`](crate::graph::GraphBlock) laid out as a
+    /// graph (falling back to highlighted source if it doesn't parse).
+    fn render_default(&self) -> String;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_block::CodeBlock;
+    use crate::diff::DiffBlock;
+    use crate::graph::GraphBlock;
+
+    #[test]
+    fn all_three_block_types_dispatch_through_the_trait_object() {
+        let code = CodeBlock::new("let x = 1;\n", Some("rust".to_string()));
+        let diff = DiffBlock::new("@@ -1 +1 @@\n-a\n+b\n", None);
+        let graph = GraphBlock::new("digraph { a -> b; }");
+
+        let blocks: Vec<&dyn FencedBlock> = vec![&code, &diff, &graph];
+        for block in blocks {
+            assert!(!block.source().is_empty());
+            assert!(!block.render_default().is_empty());
+        }
+    }
+}
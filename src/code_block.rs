@@ -0,0 +1,540 @@
+//! A renderable block of source code and its diagnostic overlay.
+//!
+//! [`CodeBlock`] owns the raw source and (optionally) a language tag. The
+//! entry point for this request is [`CodeBlock::render_with_diagnostics`],
+//! which lays out rustc-style annotations underneath the affected lines. It
+//! also implements [`crate::block::FencedBlock`], the surface it shares with
+//! [`crate::diff::DiffBlock`] and [`crate::graph::GraphBlock`].
+
+use crate::block::FencedBlock;
+use crate::diagnostics::{Diagnostic, Span};
+use crate::liveness;
+
+/// Tabs expand to the next multiple of this many columns, matching the
+/// default rustc uses when it computes underline offsets.
+const TAB_WIDTH: usize = 4;
+
+/// A block of source plus the language it should be highlighted as.
+#[derive(Debug, Clone)]
+pub struct CodeBlock {
+    pub source: String,
+    pub lang: Option<String>,
+}
+
+impl CodeBlock {
+    pub fn new(source: impl Into<String>, lang: Option<String>) -> Self {
+        CodeBlock {
+            source: source.into(),
+            lang,
+        }
+    }
+
+    /// Render the block across the range of lines `diags` touches, drawing
+    /// each diagnostic the way rustc does: the source line, then an underline
+    /// row with `^` under the primary span and `-` under the secondaries,
+    /// the label trailing the carets, and a `|` gutter linking the first and
+    /// last lines of a multi-line span.
+    ///
+    /// Byte offsets are mapped to visual columns so the carets line up even
+    /// when a line contains tabs or wide (CJK) characters, and overlapping
+    /// labels on one line are stacked onto separate rows rather than merged.
+    pub fn render_with_diagnostics(&self, diags: &[Diagnostic]) -> String {
+        if diags.is_empty() {
+            return self.source.clone();
+        }
+
+        let index = LineIndex::new(&self.source);
+
+        // Turn every span into a single-line piece, or a multi-line connector
+        // if it crosses lines, tagging the one that carries the diagnostic's
+        // label (the primary span).
+        let mut pieces: Vec<Piece> = Vec::new();
+        let mut multilines: Vec<MultiLine> = Vec::new();
+        let mut touched: Vec<usize> = Vec::new();
+
+        for diag in diags {
+            Self::collect_span(
+                &index,
+                diag.primary,
+                '^',
+                Some(diag.label.clone()),
+                &mut pieces,
+                &mut multilines,
+                &mut touched,
+            );
+            for &sec in &diag.secondary {
+                Self::collect_span(&index, sec, '-', None, &mut pieces, &mut multilines, &mut touched);
+            }
+        }
+
+        touched.sort_unstable();
+        touched.dedup();
+        let first = *touched.first().unwrap();
+        let last = *touched.last().unwrap();
+
+        let width = index.gutter_width(last);
+        let has_connector = !multilines.is_empty();
+        let mut out = String::new();
+
+        for line in first..=last {
+            let conn = connector_char(line, &multilines);
+            out.push_str(&format!(
+                "{:>width$} | {}{}\n",
+                line + 1,
+                conn_prefix(has_connector, conn),
+                index.line_text(line),
+            ));
+
+            // Underline rows for any single-line pieces anchored to this line.
+            let mut line_pieces: Vec<&Piece> =
+                pieces.iter().filter(|p| p.line == line).collect();
+            line_pieces.sort_by_key(|p| p.vstart);
+            for row in render_underline(&line_pieces) {
+                out.push_str(&format!(
+                    "{:>width$} | {}{}\n",
+                    "",
+                    conn_prefix(has_connector, ' '),
+                    row,
+                ));
+            }
+
+            // Multi-line connector anchor rows (the `____^ label` lines).
+            for ml in multilines.iter().filter(|m| m.end_line == line) {
+                out.push_str(&format!(
+                    "{:>width$} | {}\n",
+                    "",
+                    ml.anchor_row(),
+                ));
+            }
+        }
+
+        out
+    }
+
+    /// Render the block with every dead-store binding dimmed, the way a
+    /// terminal viewer fades a write that's never read: each `dead` span is
+    /// wrapped in the ANSI "faint" SGR sequence rather than removed, so the
+    /// source stays copy-pastable.
+    pub fn render_dimmed(&self, dead: &[Span]) -> String {
+        if dead.is_empty() {
+            return self.source.clone();
+        }
+        let mut spans: Vec<&Span> = dead.iter().collect();
+        spans.sort_by_key(|s| s.start);
+
+        let mut out = String::with_capacity(self.source.len() + dead.len() * 8);
+        let mut pos = 0;
+        for span in spans {
+            // Spans are expected to be non-overlapping; skip one that isn't
+            // rather than panic on a bad slice index.
+            if span.start < pos {
+                continue;
+            }
+            out.push_str(&self.source[pos..span.start]);
+            out.push_str("\x1b[2m");
+            out.push_str(&self.source[span.start..span.end]);
+            out.push_str("\x1b[0m");
+            pos = span.end;
+        }
+        out.push_str(&self.source[pos..]);
+        out
+    }
+
+    /// [`render_dimmed`](Self::render_dimmed) run over this block's own
+    /// [`liveness::find_dead_stores`] result — the one-call path for a
+    /// viewer that just wants dead-store dimming with no diagnostics.
+    pub fn render_with_dead_store_dimming(&self) -> String {
+        let dead = liveness::find_dead_stores(&self.source);
+        self.render_dimmed(&dead)
+    }
+
+    fn collect_span(
+        index: &LineIndex,
+        span: Span,
+        marker: char,
+        label: Option<String>,
+        pieces: &mut Vec<Piece>,
+        multilines: &mut Vec<MultiLine>,
+        touched: &mut Vec<usize>,
+    ) {
+        let (sl, sc) = index.locate(span.start);
+        let (mut el, mut ec) = index.locate(span.end);
+        // An exclusive end sitting exactly on a line start belongs to the end
+        // of the previous line, not column 0 of the next one — otherwise a
+        // single-line span ending on a newline looks like a multi-line span.
+        if span.end > span.start && ec == 0 && el > sl {
+            el -= 1;
+            ec = index.line_len_bytes(el);
+        }
+        touched.push(sl);
+        touched.push(el);
+
+        if sl == el {
+            let vstart = index.visual_col(sl, sc);
+            let vend = index.visual_col(el, ec).max(vstart + 1);
+            pieces.push(Piece { line: sl, vstart, vend, marker, label });
+        } else {
+            // Multi-line span: anchor the label to the end line and draw a
+            // `|` gutter through the intervening lines.
+            let vend = index.visual_col(el, ec).max(1);
+            multilines.push(MultiLine {
+                start_line: sl,
+                end_line: el,
+                end_col: vend,
+                marker,
+                label,
+            });
+        }
+    }
+}
+
+impl FencedBlock for CodeBlock {
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn render_default(&self) -> String {
+        self.render_with_dead_store_dimming()
+    }
+}
+
+/// One single-line underline fragment.
+struct Piece {
+    line: usize,
+    vstart: usize,
+    vend: usize,
+    marker: char,
+    label: Option<String>,
+}
+
+/// A span that straddles more than one line.
+struct MultiLine {
+    start_line: usize,
+    end_line: usize,
+    end_col: usize,
+    marker: char,
+    label: Option<String>,
+}
+
+impl MultiLine {
+    /// The `|____^ label` row drawn under the final line of the span.
+    fn anchor_row(&self) -> String {
+        // The `|` aligns with the gutter connector column; the underscores
+        // then span the two-column connector prefix plus the line content up
+        // to the caret, so `^` lands under the span's final character.
+        let mut row = String::from("|");
+        row.extend(std::iter::repeat_n('_', self.end_col));
+        row.push(self.marker);
+        if let Some(label) = &self.label {
+            row.push(' ');
+            row.push_str(label);
+        }
+        row
+    }
+}
+
+/// The connector glyph drawn in the gutter column for a given line.
+fn connector_char(line: usize, multilines: &[MultiLine]) -> char {
+    for ml in multilines {
+        if line == ml.start_line {
+            return '/';
+        }
+        if line > ml.start_line && line <= ml.end_line {
+            return '|';
+        }
+    }
+    ' '
+}
+
+fn conn_prefix(has_connector: bool, c: char) -> String {
+    if has_connector {
+        format!("{} ", c)
+    } else {
+        String::new()
+    }
+}
+
+/// Build the underline rows for the pieces anchored to a single line,
+/// stacking labels onto separate rows when more than one wants a label so
+/// they don't collide.
+fn render_underline(pieces: &[&Piece]) -> Vec<String> {
+    if pieces.is_empty() {
+        return Vec::new();
+    }
+    let max_col = pieces.iter().map(|p| p.vend).max().unwrap_or(0);
+    let mut caret: Vec<char> = vec![' '; max_col];
+    for p in pieces {
+        for slot in caret[p.vstart..p.vend].iter_mut() {
+            *slot = p.marker;
+        }
+    }
+
+    // Collect the labeled pieces left-to-right; `caret` already carries the
+    // markers for every piece (labeled or not).
+    let labeled: Vec<&Piece> = pieces
+        .iter()
+        .copied()
+        .filter(|p| p.label.is_some())
+        .collect();
+    let mut rows = Vec::new();
+
+    if labeled.is_empty() {
+        rows.push(trim_end(&caret));
+        return rows;
+    }
+
+    // The rightmost label trails the carets on the underline row itself.
+    let mut top = caret.clone();
+    top.push(' ');
+    top.extend(label_of(labeled[labeled.len() - 1]).chars());
+    rows.push(trim_end(&top));
+
+    // Any remaining labels (left of the rightmost) stack below, the same
+    // staircase rustc draws: a connector row with a `|` under every label
+    // still waiting, then one row per label — rightmost-of-the-rest first —
+    // where that label's `|` turns into its own text while the `|`s to its
+    // left carry on, so no label ever shares a row with another's connector.
+    let remaining = &labeled[..labeled.len() - 1];
+    if !remaining.is_empty() {
+        rows.push(connector_row(remaining, max_col));
+        for i in (0..remaining.len()).rev() {
+            rows.push(label_row(&remaining[..i], remaining[i]));
+        }
+    }
+    rows
+}
+
+/// A row of bare `|` connectors, one under each of `pending`'s columns.
+fn connector_row(pending: &[&Piece], max_col: usize) -> String {
+    let mut row = vec![' '; max_col];
+    for p in pending {
+        if p.vstart < row.len() {
+            row[p.vstart] = '|';
+        }
+    }
+    trim_end(&row)
+}
+
+/// The row that finalizes `label_piece`: a `|` under each of `pending`
+/// (labels still waiting further left) and `label_piece`'s own text
+/// starting right at its column.
+fn label_row(pending: &[&Piece], label_piece: &Piece) -> String {
+    let mut row = vec![' '; label_piece.vstart];
+    for p in pending {
+        if p.vstart < row.len() {
+            row[p.vstart] = '|';
+        }
+    }
+    let mut line = trim_end(&row);
+    while line.chars().count() < label_piece.vstart {
+        line.push(' ');
+    }
+    line.push_str(label_of(label_piece));
+    line
+}
+
+fn label_of(p: &Piece) -> &str {
+    p.label.as_deref().unwrap_or_default()
+}
+
+fn trim_end(chars: &[char]) -> String {
+    let s: String = chars.iter().collect();
+    s.trim_end().to_string()
+}
+
+/// Precomputed line-start offsets, used to translate byte offsets into
+/// `(line, column)` pairs and to measure visual columns.
+struct LineIndex<'a> {
+    source: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(source: &'a str) -> Self {
+        let mut starts = vec![0];
+        for (i, b) in source.bytes().enumerate() {
+            if b == b'\n' {
+                starts.push(i + 1);
+            }
+        }
+        LineIndex { source, starts }
+    }
+
+    /// Map a byte offset to `(line, byte offset within line)`.
+    fn locate(&self, byte: usize) -> (usize, usize) {
+        let line = match self.starts.binary_search(&byte) {
+            Ok(l) => l,
+            Err(l) => l - 1,
+        };
+        (line, byte - self.starts[line])
+    }
+
+    fn line_text(&self, line: usize) -> &'a str {
+        let start = self.starts[line];
+        let end = self
+            .starts
+            .get(line + 1)
+            .map(|&e| e - 1)
+            .unwrap_or(self.source.len());
+        self.source[start..end].trim_end_matches(['\r', '\n'])
+    }
+
+    /// The visual column of a byte offset within `line`, expanding tabs to
+    /// [`TAB_WIDTH`] stops and counting wide characters as two columns.
+    fn visual_col(&self, line: usize, byte_in_line: usize) -> usize {
+        let text = self.line_text(line);
+        let mut col = 0;
+        let mut byte = 0;
+        for c in text.chars() {
+            if byte >= byte_in_line {
+                break;
+            }
+            if c == '\t' {
+                col += TAB_WIDTH - (col % TAB_WIDTH);
+            } else {
+                col += char_width(c);
+            }
+            byte += c.len_utf8();
+        }
+        col
+    }
+
+    fn line_len_bytes(&self, line: usize) -> usize {
+        self.line_text(line).len()
+    }
+
+    fn gutter_width(&self, last_line: usize) -> usize {
+        (last_line + 1).to_string().len()
+    }
+}
+
+/// Visual width of a character: two columns for East Asian wide / fullwidth
+/// code points, one otherwise. Control characters are treated as width one.
+fn char_width(c: char) -> usize {
+    if is_wide(c) {
+        2
+    } else {
+        1
+    }
+}
+
+fn is_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F   // Hangul Jamo
+        | 0x2E80..=0x303E // CJK radicals, Kangxi
+        | 0x3041..=0x33FF // Hiragana, Katakana, CJK symbols
+        | 0x3400..=0x4DBF // CJK Extension A
+        | 0x4E00..=0x9FFF // CJK Unified Ideographs
+        | 0xA000..=0xA4CF // Yi
+        | 0xAC00..=0xD7A3 // Hangul Syllables
+        | 0xF900..=0xFAFF // CJK Compatibility Ideographs
+        | 0xFE30..=0xFE4F // CJK Compatibility Forms
+        | 0xFF00..=0xFF60 // Fullwidth Forms
+        | 0xFFE0..=0xFFE6
+        | 0x20000..=0x3FFFD) // CJK Extension B+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Severity;
+
+    #[test]
+    fn single_line_caret_and_label() {
+        let block = CodeBlock::new("let x = 1;\n", Some("rust".into()));
+        // underline the `x`
+        let diag = Diagnostic::new(Severity::Warning, Span::new(4, 5), "unused variable");
+        let out = block.render_with_diagnostics(&[diag]);
+        assert!(out.contains("1 | let x = 1;"));
+        assert!(out.contains("^ unused variable"));
+    }
+
+    #[test]
+    fn tabs_shift_the_caret() {
+        // A leading tab expands to four columns, so the caret under `x`
+        // (byte offset 5) must sit at visual column 8, not 5.
+        let block = CodeBlock::new("\tlet x;\n", Some("rust".into()));
+        let diag = Diagnostic::new(Severity::Error, Span::new(5, 6), "here");
+        let out = block.render_with_diagnostics(&[diag]);
+        let underline = out.lines().find(|l| l.contains('^')).unwrap();
+        let caret = underline.find('^').unwrap();
+        let bar = underline.find('|').unwrap();
+        // four tab columns + "let " == 8 columns after the gutter.
+        assert_eq!(caret - bar - 2, 8);
+    }
+
+    #[test]
+    fn secondary_spans_use_dashes() {
+        let block = CodeBlock::new("a + b\n", None);
+        let diag = Diagnostic::new(Severity::Info, Span::new(0, 1), "lhs")
+            .with_secondary(Span::new(4, 5));
+        let out = block.render_with_diagnostics(&[diag]);
+        assert!(out.contains('-')); // secondary span
+        assert!(out.contains('^')); // primary span
+        assert!(out.contains("lhs"));
+    }
+
+    #[test]
+    fn two_labels_on_one_line_stack_onto_separate_rows() {
+        // `x` and `y` each get their own single-char, labeled span; the
+        // rightmost ("second") trails the caret row, and "first" must land
+        // on its own row below a bare `|` connector, not share one with it.
+        let block = CodeBlock::new("let x = y;\n", None);
+        let first = Diagnostic::new(Severity::Warning, Span::new(4, 5), "first");
+        let second = Diagnostic::new(Severity::Warning, Span::new(8, 9), "second");
+        let out = block.render_with_diagnostics(&[first, second]);
+        let lines: Vec<&str> = out.lines().collect();
+
+        // Strip the `<num> | ` gutter so the `|` it always carries doesn't
+        // get confused with an underline-row connector.
+        let content = |line: &str| line.split_once(" | ").unwrap().1.to_string();
+
+        let caret_row = lines.iter().position(|l| l.contains('^')).unwrap();
+        assert!(content(lines[caret_row]).ends_with("^   ^ second"));
+
+        let connector_row = content(lines[caret_row + 1]);
+        assert!(connector_row.trim_end().ends_with('|'));
+        assert!(!connector_row.contains("first"));
+
+        let label_row = content(lines[caret_row + 2]);
+        assert!(label_row.trim_end().ends_with("first"));
+        assert!(!label_row.contains('|'));
+    }
+
+    #[test]
+    fn exclusive_end_on_newline_stays_single_line() {
+        // Span (0, 4) covers "abc" plus the trailing newline; it must under-
+        // line line 1, not spill onto line 2.
+        let block = CodeBlock::new("abc\ndef\n", None);
+        let diag = Diagnostic::new(Severity::Warning, Span::new(0, 4), "here");
+        let out = block.render_with_diagnostics(&[diag]);
+        assert!(out.contains("1 | abc"));
+        assert!(!out.contains("2 | def")); // must not drag in the next line
+        assert!(out.contains("^^^ here"));
+    }
+
+    #[test]
+    fn multiline_span_draws_a_gutter() {
+        let src = "fn f(\n    x: i32,\n) {}\n";
+        let block = CodeBlock::new(src, Some("rust".into()));
+        let diag = Diagnostic::new(Severity::Error, Span::new(3, 17), "signature");
+        let out = block.render_with_diagnostics(&[diag]);
+        assert!(out.contains('/')); // connector opens on the start line
+        assert!(out.contains("^ signature"));
+    }
+
+    #[test]
+    fn dead_store_is_dimmed_not_removed() {
+        let block = CodeBlock::new("fn f() {\n    let x = 1;\n}\n", Some("rust".into()));
+        let out = block.render_with_dead_store_dimming();
+        assert!(out.contains("\x1b[2mx\x1b[0m"));
+        assert!(out.contains("let \x1b[2mx\x1b[0m = 1;")); // source text is preserved, just wrapped
+    }
+
+    #[test]
+    fn no_dead_stores_renders_source_unchanged() {
+        let src = "fn f() {\n    let x = 1;\n    println!(\"{}\", x);\n}\n";
+        let block = CodeBlock::new(src, Some("rust".into()));
+        assert_eq!(block.render_with_dead_store_dimming(), src);
+    }
+}
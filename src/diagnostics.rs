@@ -0,0 +1,82 @@
+//! Diagnostic annotations that can be layered over a rendered code block.
+//!
+//! A [`Diagnostic`] pins a *primary* byte span plus any number of *secondary*
+//! spans to a label and a [`Severity`]. The renderer in
+//! [`crate::code_block`] draws them the way rustc does — carets under the
+//! primary span, dashes under the secondaries, label text trailing the
+//! underline, and a `|` gutter bridging the first and last lines of a
+//! multi-line span.
+
+/// A half-open range of byte offsets into the block's source, `[start, end)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        debug_assert!(start <= end, "span start must not exceed end");
+        Span { start, end }
+    }
+}
+
+/// How loud a diagnostic is; also selects the underline color downstream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl Severity {
+    /// The ANSI SGR foreground code the viewer tints this severity's
+    /// underline with (red/yellow/blue/cyan, matching rustc's palette).
+    pub fn ansi_color(self) -> u8 {
+        match self {
+            Severity::Error => 31,
+            Severity::Warning => 33,
+            Severity::Info => 34,
+            Severity::Hint => 36,
+        }
+    }
+
+    /// The caret/dash marker is uniform across severities — rustc varies the
+    /// *color*, not the glyph — so this only exists for callers that want a
+    /// textual tag (e.g. `error`, `warning`).
+    pub fn tag(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+            Severity::Hint => "hint",
+        }
+    }
+}
+
+/// One annotation attached to a rendered block.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub primary: Span,
+    pub secondary: Vec<Span>,
+    pub label: String,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, primary: Span, label: impl Into<String>) -> Self {
+        Diagnostic {
+            severity,
+            primary,
+            secondary: Vec::new(),
+            label: label.into(),
+        }
+    }
+
+    /// Attach a secondary span (rendered with `-` rather than `^`).
+    pub fn with_secondary(mut self, span: Span) -> Self {
+        self.secondary.push(span);
+        self
+    }
+}
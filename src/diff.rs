@@ -0,0 +1,365 @@
+//! Unified-diff aware rendering.
+//!
+//! [`DiffBlock`] detects a unified-diff/`git diff` payload, splits it into
+//! hunks, and renders each line with the standard add/remove/context
+//! background tint while still syntax-highlighting its code content via
+//! [`crate::highlight`]: the diff marker (`+`, `-`, or a leading space) is
+//! stripped before the rest of the line is tokenized, then put back at its
+//! original column so the marker itself is never re-colored. Long runs of
+//! unchanged context lines within a hunk collapse behind a fold note
+//! rather than printing every line.
+
+use crate::block::FencedBlock;
+use crate::highlight;
+
+/// A run of context lines longer than this collapses to a fold note,
+/// keeping [`FOLD_EDGE`] lines visible on either side — the same shape
+/// GitHub's diff viewer uses for an "Expand" widget.
+const FOLD_THRESHOLD: usize = 6;
+const FOLD_EDGE: usize = 3;
+
+/// Whether `source` looks like unified-diff output: at least one `@@ ...
+/// @@` hunk header. That's the one line shape a unified diff always has
+/// and ordinary source never does, so it's enough on its own — the `---`/
+/// `+++` file header lines are optional (a hunk pasted without its header
+/// is still a diff).
+pub fn is_unified_diff(source: &str) -> bool {
+    source
+        .lines()
+        .any(|line| line.starts_with("@@ ") && line[3..].find("@@").is_some())
+}
+
+/// A diff payload plus the language its content lines should be
+/// highlighted as, mirroring [`crate::code_block::CodeBlock`] and, like it,
+/// implementing [`crate::block::FencedBlock`].
+#[derive(Debug, Clone)]
+pub struct DiffBlock {
+    pub source: String,
+    pub lang: Option<String>,
+}
+
+impl DiffBlock {
+    pub fn new(source: impl Into<String>, lang: Option<String>) -> Self {
+        DiffBlock {
+            source: source.into(),
+            lang,
+        }
+    }
+
+    /// Render the diff: a gutter with the original/new line numbers, the
+    /// diff marker, and the highlighted line content.
+    pub fn render(&self) -> String {
+        let (file_header, hunks) = parse(&self.source);
+        let (old_w, new_w) = gutter_widths(&hunks);
+
+        let mut out = String::new();
+        if let Some((old_file, new_file)) = file_header {
+            out.push_str(&old_file);
+            out.push('\n');
+            out.push_str(&new_file);
+            out.push('\n');
+        }
+        for hunk in &hunks {
+            out.push_str(&hunk.header);
+            out.push('\n');
+            for item in fold_context(&hunk.lines) {
+                match item {
+                    RenderItem::Line(line) => {
+                        out.push_str(&render_line(line, old_w, new_w, self.lang.as_deref()))
+                    }
+                    RenderItem::Fold(hidden) => out.push_str(&render_fold(hidden, old_w, new_w)),
+                }
+            }
+        }
+        out
+    }
+}
+
+impl FencedBlock for DiffBlock {
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn render_default(&self) -> String {
+        self.render()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Added,
+    Removed,
+    Context,
+    /// A line the diff format itself emits that isn't part of either file,
+    /// e.g. `\ No newline at end of file`.
+    Meta,
+}
+
+struct DiffLine {
+    kind: LineKind,
+    old_no: Option<usize>,
+    new_no: Option<usize>,
+    marker: char,
+    content: String,
+}
+
+struct Hunk {
+    header: String,
+    lines: Vec<DiffLine>,
+}
+
+/// Split `source` into its (optional) `---`/`+++` file header and its
+/// hunks, tracking old/new line numbers as it walks each hunk's lines.
+fn parse(source: &str) -> (Option<(String, String)>, Vec<Hunk>) {
+    let lines: Vec<&str> = source.lines().collect();
+
+    let mut file_header = None;
+    let mut cursor = 0;
+    while cursor < lines.len() && !lines[cursor].starts_with("@@ ") {
+        if lines[cursor].starts_with("--- ") {
+            if let Some(next) = lines.get(cursor + 1) {
+                if next.starts_with("+++ ") {
+                    file_header = Some((lines[cursor].to_string(), next.to_string()));
+                }
+            }
+        }
+        cursor += 1;
+    }
+
+    let mut hunks = Vec::new();
+    let mut current: Option<(Hunk, usize, usize)> = None;
+    let mut old_no = 0;
+    let mut new_no = 0;
+
+    for line in &lines[cursor..] {
+        let line = *line;
+        if line.starts_with("@@ ") {
+            if let Some((hunk, _, _)) = current.take() {
+                hunks.push(hunk);
+            }
+            current = start_hunk(line);
+            if let Some((_, o, n)) = current.as_ref() {
+                old_no = *o;
+                new_no = *n;
+            }
+            continue;
+        }
+        let Some((hunk, _, _)) = current.as_mut() else {
+            continue;
+        };
+        let (marker, rest) = split_marker(line);
+        let diff_line = match marker {
+            '+' => {
+                let n = new_no;
+                new_no += 1;
+                DiffLine {
+                    kind: LineKind::Added,
+                    old_no: None,
+                    new_no: Some(n),
+                    marker,
+                    content: rest.to_string(),
+                }
+            }
+            '-' => {
+                let n = old_no;
+                old_no += 1;
+                DiffLine {
+                    kind: LineKind::Removed,
+                    old_no: Some(n),
+                    new_no: None,
+                    marker,
+                    content: rest.to_string(),
+                }
+            }
+            '\\' => DiffLine {
+                kind: LineKind::Meta,
+                old_no: None,
+                new_no: None,
+                marker,
+                content: rest.to_string(),
+            },
+            _ => {
+                let o = old_no;
+                let n = new_no;
+                old_no += 1;
+                new_no += 1;
+                DiffLine {
+                    kind: LineKind::Context,
+                    old_no: Some(o),
+                    new_no: Some(n),
+                    marker: ' ',
+                    content: rest.to_string(),
+                }
+            }
+        };
+        hunk.lines.push(diff_line);
+    }
+    if let Some((hunk, _, _)) = current.take() {
+        hunks.push(hunk);
+    }
+
+    (file_header, hunks)
+}
+
+/// Parse a `@@ -old_start,old_count +new_start,new_count @@` header,
+/// returning the hunk shell plus the starting old/new line numbers.
+fn start_hunk(line: &str) -> Option<(Hunk, usize, usize)> {
+    let rest = line.strip_prefix("@@ ")?;
+    let close = rest.find(" @@")?;
+    let ranges = &rest[..close];
+    let mut parts = ranges.split(' ');
+    let old = parts.next()?.strip_prefix('-')?;
+    let new = parts.next()?.strip_prefix('+')?;
+    let (old_start, _) = parse_range(old)?;
+    let (new_start, _) = parse_range(new)?;
+    Some((
+        Hunk {
+            header: line.to_string(),
+            lines: Vec::new(),
+        },
+        old_start,
+        new_start,
+    ))
+}
+
+/// `"12,5"` -> `(12, 5)`; a bare `"12"` (count elided, meaning 1) -> `(12, 1)`.
+fn parse_range(s: &str) -> Option<(usize, usize)> {
+    match s.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((s.parse().ok()?, 1)),
+    }
+}
+
+/// Split a hunk body line into its leading diff marker and the rest, the
+/// part that gets syntax-highlighted. A line with no recognized marker
+/// (some tools emit a bare empty line for an empty context line) is
+/// treated as context.
+fn split_marker(line: &str) -> (char, &str) {
+    let mut chars = line.chars();
+    match chars.next() {
+        Some(c @ ('+' | '-' | ' ' | '\\')) => (c, chars.as_str()),
+        _ => (' ', line),
+    }
+}
+
+enum RenderItem<'a> {
+    Line(&'a DiffLine),
+    Fold(usize),
+}
+
+/// Collapse any run of [`LineKind::Context`] lines longer than
+/// [`FOLD_THRESHOLD`] down to [`FOLD_EDGE`] lines on each side plus a fold
+/// note for what's hidden between them.
+fn fold_context(lines: &[DiffLine]) -> Vec<RenderItem<'_>> {
+    let mut out = Vec::with_capacity(lines.len());
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].kind != LineKind::Context {
+            out.push(RenderItem::Line(&lines[i]));
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < lines.len() && lines[i].kind == LineKind::Context {
+            i += 1;
+        }
+        let run = &lines[start..i];
+        if run.len() > FOLD_THRESHOLD {
+            out.extend(run[..FOLD_EDGE].iter().map(RenderItem::Line));
+            out.push(RenderItem::Fold(run.len() - 2 * FOLD_EDGE));
+            out.extend(run[run.len() - FOLD_EDGE..].iter().map(RenderItem::Line));
+        } else {
+            out.extend(run.iter().map(RenderItem::Line));
+        }
+    }
+    out
+}
+
+fn gutter_widths(hunks: &[Hunk]) -> (usize, usize) {
+    let max_old = hunks
+        .iter()
+        .flat_map(|h| h.lines.iter())
+        .filter_map(|l| l.old_no)
+        .max()
+        .unwrap_or(0);
+    let max_new = hunks
+        .iter()
+        .flat_map(|h| h.lines.iter())
+        .filter_map(|l| l.new_no)
+        .max()
+        .unwrap_or(0);
+    (max_old.to_string().len().max(1), max_new.to_string().len().max(1))
+}
+
+fn render_line(line: &DiffLine, old_w: usize, new_w: usize, lang: Option<&str>) -> String {
+    let old_col = line.old_no.map(|n| n.to_string()).unwrap_or_default();
+    let new_col = line.new_no.map(|n| n.to_string()).unwrap_or_default();
+    let highlighted = highlight::highlight_line(&line.content, lang);
+    let (bg, reset) = match line.kind {
+        LineKind::Added => ("\x1b[42m", "\x1b[0m"),
+        LineKind::Removed => ("\x1b[41m", "\x1b[0m"),
+        LineKind::Context | LineKind::Meta => ("", ""),
+    };
+    let marker = line.marker;
+    format!("{old_col:>old_w$} {new_col:>new_w$} {bg}{marker}{highlighted}{reset}\n")
+}
+
+fn render_fold(hidden: usize, old_w: usize, new_w: usize) -> String {
+    let plural = if hidden == 1 { "" } else { "s" };
+    format!("{:>old_w$} {:>new_w$} \u{22ef} {hidden} unchanged line{plural} \u{22ef}\n", "", "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,3 +1,3 @@\n fn main() {\n-    let multiply = |x, y| x * y;\n+    let multiply = |x: i32, y: i32| x * y;\n }\n";
+
+    #[test]
+    fn detects_unified_diff() {
+        assert!(is_unified_diff(SAMPLE));
+        assert!(!is_unified_diff("fn main() {}\n"));
+    }
+
+    #[test]
+    fn gutter_shows_old_and_new_line_numbers() {
+        // No `lang` means no highlighting codes, so the gutter layout is
+        // easy to assert on directly.
+        let block = DiffBlock::new(SAMPLE, None);
+        let out = block.render();
+        assert!(out.contains("1 1  fn main() {"));
+        assert!(out.contains("2   \x1b[41m-    let multiply = |x, y| x * y;\x1b[0m"));
+        assert!(out.contains("  2 \x1b[42m+    let multiply = |x: i32, y: i32| x * y;\x1b[0m"));
+    }
+
+    #[test]
+    fn removed_and_added_lines_are_tinted_and_still_highlighted() {
+        let block = DiffBlock::new(SAMPLE, Some("rust".into()));
+        let out = block.render();
+        assert!(out.contains("\x1b[41m-"));
+        assert!(out.contains("\x1b[42m+"));
+        // The marker is stripped before tokenizing, so `let` highlights
+        // inside both the removed and the added line.
+        assert_eq!(out.matches("\x1b[35mlet\x1b[0m").count(), 2);
+    }
+
+    #[test]
+    fn long_context_run_folds() {
+        let mut src = String::from("@@ -1,12 +1,12 @@\n");
+        for i in 1..=12 {
+            src.push_str(&format!(" line {i}\n"));
+        }
+        let block = DiffBlock::new(src, None);
+        let out = block.render();
+        assert!(out.contains("unchanged lines"));
+        assert!(out.contains("line 1\n"));
+        assert!(out.contains("line 12\n"));
+        assert!(!out.contains("line 6\n")); // folded away in the middle
+    }
+
+    #[test]
+    fn non_diff_input_produces_no_hunks() {
+        let block = DiffBlock::new("fn main() {}\n", Some("rust".into()));
+        assert_eq!(block.render(), "");
+    }
+}
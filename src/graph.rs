@@ -0,0 +1,685 @@
+//! Fenced `dot`/Graphviz block rendering.
+//!
+//! [`GraphBlock`] mirrors the `{ source, ... }` + `new` shape
+//! [`crate::code_block::CodeBlock`] and [`crate::diff::DiffBlock`] already
+//! use, and like them implements [`crate::block::FencedBlock`] — so a
+//! caller dispatching on a fenced block's language tag can hold any of the
+//! three behind `&dyn FencedBlock` for the default render, reaching for the
+//! concrete type only when it needs [`View`]'s extra control. What's
+//! specific to a graph block is that toggle: the caller picks whether it
+//! wants the raw DOT text or the laid-out graph, the same choice a viewer
+//! would expose as a "source"/"rendered" tab.
+//!
+//! Parsing covers a minimal subset of DOT: a `digraph` (directed, `->`) or
+//! `graph` (undirected, `--`) header, node and edge statements, and
+//! `label`/`shape`/`color` attributes. `subgraph` blocks, ports, and HTML
+//! labels aren't supported — the same "heuristic, not a resolver" scope
+//! [`crate::rules`] and [`crate::liveness`] document for their own corners
+//! of the language. A source that doesn't parse falls back to
+//! [`View::Source`] rather than producing a blank or broken render.
+
+use crate::block::FencedBlock;
+use crate::highlight;
+
+/// Which of a [`GraphBlock`]'s two renderings the caller wants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    /// The raw DOT text, syntax highlighted like any other fenced block.
+    Source,
+    /// The graph laid out as boxes and connectors. Falls back to
+    /// [`View::Source`] if the text doesn't parse as DOT.
+    Rendered,
+}
+
+/// A fenced `dot`/`graphviz` block.
+#[derive(Debug, Clone)]
+pub struct GraphBlock {
+    pub source: String,
+}
+
+impl GraphBlock {
+    pub fn new(source: impl Into<String>) -> Self {
+        GraphBlock {
+            source: source.into(),
+        }
+    }
+
+    /// Render the block as the requested [`View`].
+    pub fn render(&self, view: View) -> String {
+        match view {
+            View::Source => self.highlighted_source(),
+            View::Rendered => match parse(&self.source) {
+                Ok(graph) => layout(&graph),
+                Err(_) => self.highlighted_source(),
+            },
+        }
+    }
+
+    fn highlighted_source(&self) -> String {
+        self.source
+            .lines()
+            .map(|line| highlight::highlight_line(line, Some("dot")))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl FencedBlock for GraphBlock {
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn render_default(&self) -> String {
+        self.render(View::Rendered)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GraphKind {
+    Directed,
+    Undirected,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Attrs {
+    label: Option<String>,
+    shape: Option<String>,
+    color: Option<String>,
+}
+
+impl Attrs {
+    fn set(&mut self, key: &str, value: String) {
+        match key {
+            "label" => self.label = Some(value),
+            "shape" => self.shape = Some(value),
+            "color" => self.color = Some(value),
+            // Any other attribute (`rankdir`, `style`, ...) is out of scope
+            // for this minimal layout and is silently dropped.
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    id: String,
+    attrs: Attrs,
+}
+
+#[derive(Debug, Clone)]
+struct Edge {
+    from: String,
+    to: String,
+    attrs: Attrs,
+}
+
+#[derive(Debug)]
+struct Graph {
+    kind: GraphKind,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+/// Why [`parse`] gave up. Deliberately not [`pub`] — the only thing a
+/// caller does with a parse failure is fall back to [`View::Source`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParseError {
+    MissingHeader,
+    UnterminatedBlock,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tok {
+    Ident(String),
+    Str(String),
+    Arrow,
+    DashDash,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Comma,
+    Equals,
+    Semi,
+}
+
+fn lex(src: &str) -> Result<Vec<Tok>, ParseError> {
+    let bytes = src.as_bytes();
+    let mut toks = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match b {
+            b' ' | b'\t' | b'\r' | b'\n' => i += 1,
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i + 1 < bytes.len() && !(bytes[i] == b'*' && bytes[i + 1] == b'/') {
+                    i += 1;
+                }
+                i = (i + 2).min(bytes.len());
+            }
+            b'{' => {
+                toks.push(Tok::LBrace);
+                i += 1;
+            }
+            b'}' => {
+                toks.push(Tok::RBrace);
+                i += 1;
+            }
+            b'[' => {
+                toks.push(Tok::LBracket);
+                i += 1;
+            }
+            b']' => {
+                toks.push(Tok::RBracket);
+                i += 1;
+            }
+            b',' => {
+                toks.push(Tok::Comma);
+                i += 1;
+            }
+            b'=' => {
+                toks.push(Tok::Equals);
+                i += 1;
+            }
+            b';' => {
+                toks.push(Tok::Semi);
+                i += 1;
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'>') => {
+                toks.push(Tok::Arrow);
+                i += 2;
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                toks.push(Tok::DashDash);
+                i += 2;
+            }
+            b'"' => {
+                let start = i + 1;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                if i >= bytes.len() {
+                    return Err(ParseError::UnterminatedBlock);
+                }
+                toks.push(Tok::Str(src[start..i].to_string()));
+                i += 1;
+            }
+            _ if is_word_byte(b) => {
+                let start = i;
+                while i < bytes.len() && is_word_byte(bytes[i]) {
+                    i += 1;
+                }
+                toks.push(Tok::Ident(src[start..i].to_string()));
+            }
+            _ => i += 1,
+        }
+    }
+    Ok(toks)
+}
+
+fn is_word_byte(b: u8) -> bool {
+    b == b'_' || b.is_ascii_alphanumeric() || b == b'.'
+}
+
+/// Parse `source` as a minimal DOT graph.
+fn parse(source: &str) -> Result<Graph, ParseError> {
+    let toks = lex(source)?;
+    let mut p = Cursor { toks: &toks, pos: 0 };
+
+    if p.eat_ident_ci("strict") {
+        // `strict` only affects edge deduplication, which this layout
+        // doesn't attempt; accept and ignore the keyword.
+    }
+    let kind = if p.eat_ident_ci("digraph") {
+        GraphKind::Directed
+    } else if p.eat_ident_ci("graph") {
+        GraphKind::Undirected
+    } else {
+        return Err(ParseError::MissingHeader);
+    };
+    // Optional graph name.
+    p.eat_any_ident();
+    if !p.eat(&Tok::LBrace) {
+        return Err(ParseError::MissingHeader);
+    }
+
+    let mut graph = Graph {
+        kind,
+        nodes: Vec::new(),
+        edges: Vec::new(),
+    };
+
+    loop {
+        if p.eat(&Tok::RBrace) {
+            return Ok(graph);
+        }
+        if p.at_end() {
+            return Err(ParseError::UnterminatedBlock);
+        }
+        parse_stmt(&mut p, &mut graph);
+        p.eat(&Tok::Semi);
+    }
+}
+
+struct Cursor<'a> {
+    toks: &'a [Tok],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn at_end(&self) -> bool {
+        self.pos >= self.toks.len()
+    }
+
+    fn peek(&self) -> Option<&Tok> {
+        self.toks.get(self.pos)
+    }
+
+    fn eat(&mut self, want: &Tok) -> bool {
+        if self.peek() == Some(want) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_ident_ci(&mut self, word: &str) -> bool {
+        if let Some(Tok::Ident(s)) = self.peek() {
+            if s.eq_ignore_ascii_case(word) {
+                self.pos += 1;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Consume the next token as an identifier-ish label, if there is one:
+    /// a bare word or a quoted string.
+    fn eat_any_ident(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Tok::Ident(s)) => {
+                let s = s.clone();
+                self.pos += 1;
+                Some(s)
+            }
+            Some(Tok::Str(s)) => {
+                let s = s.clone();
+                self.pos += 1;
+                Some(s)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// One node or edge statement, e.g. `A [shape=box];` or `A -> B -> C;`.
+/// A bare `id = id` graph attribute (e.g. `rankdir=LR`) is consumed and
+/// dropped — it's not a node or edge.
+fn parse_stmt(p: &mut Cursor<'_>, graph: &mut Graph) {
+    let Some(first) = p.eat_any_ident() else {
+        // Stray token (e.g. an attribute list with no subject); skip it so
+        // a single malformed statement doesn't wedge the whole parse.
+        p.pos += 1;
+        return;
+    };
+
+    if p.eat(&Tok::Equals) {
+        p.eat_any_ident();
+        return;
+    }
+
+    let mut chain = vec![first];
+    loop {
+        if p.eat(&Tok::Arrow) || p.eat(&Tok::DashDash) {
+            if let Some(next) = p.eat_any_ident() {
+                chain.push(next);
+                continue;
+            }
+        }
+        break;
+    }
+
+    let attrs = parse_attrs(p);
+
+    if chain.len() == 1 {
+        let node = get_or_insert(&mut graph.nodes, &chain[0]);
+        merge_attrs(&mut node.attrs, &attrs);
+    } else {
+        for pair in chain.windows(2) {
+            graph.edges.push(Edge {
+                from: pair[0].clone(),
+                to: pair[1].clone(),
+                attrs: attrs.clone(),
+            });
+            get_or_insert(&mut graph.nodes, &pair[0]);
+            get_or_insert(&mut graph.nodes, &pair[1]);
+        }
+    }
+}
+
+fn parse_attrs(p: &mut Cursor<'_>) -> Attrs {
+    let mut attrs = Attrs::default();
+    if !p.eat(&Tok::LBracket) {
+        return attrs;
+    }
+    loop {
+        if p.eat(&Tok::RBracket) || p.at_end() {
+            break;
+        }
+        let Some(key) = p.eat_any_ident() else {
+            p.pos += 1;
+            continue;
+        };
+        if p.eat(&Tok::Equals) {
+            if let Some(value) = p.eat_any_ident() {
+                attrs.set(&key.to_ascii_lowercase(), value);
+            }
+        }
+        p.eat(&Tok::Comma);
+    }
+    attrs
+}
+
+fn merge_attrs(into: &mut Attrs, from: &Attrs) {
+    if from.label.is_some() {
+        into.label = from.label.clone();
+    }
+    if from.shape.is_some() {
+        into.shape = from.shape.clone();
+    }
+    if from.color.is_some() {
+        into.color = from.color.clone();
+    }
+}
+
+fn get_or_insert<'a>(nodes: &'a mut Vec<Node>, id: &str) -> &'a mut Node {
+    if let Some(i) = nodes.iter().position(|n| n.id == id) {
+        return &mut nodes[i];
+    }
+    nodes.push(Node {
+        id: id.to_string(),
+        attrs: Attrs::default(),
+    });
+    nodes.last_mut().unwrap()
+}
+
+/// Lay the graph out as ranked rows of boxes with connectors between
+/// adjacent ranks, falling back to a plain edge list for anything a
+/// two-dimensional box layout can't depict (same-rank or rank-skipping
+/// edges, and cycles).
+fn layout(graph: &Graph) -> String {
+    let ranks = assign_ranks(graph);
+    let max_rank = ranks.values().copied().max().unwrap_or(0);
+
+    let mut rows: Vec<Vec<&Node>> = vec![Vec::new(); max_rank + 1];
+    for node in &graph.nodes {
+        rows[ranks[&node.id]].push(node);
+    }
+
+    let arrow = match graph.kind {
+        GraphKind::Directed => "->",
+        GraphKind::Undirected => "--",
+    };
+
+    let mut out = String::new();
+    let header = match graph.kind {
+        GraphKind::Directed => "digraph",
+        GraphKind::Undirected => "graph",
+    };
+    out.push_str(header);
+    out.push('\n');
+
+    let mut spillover: Vec<&Edge> = Vec::new();
+    for (r, row) in rows.iter().enumerate() {
+        let rendered: Vec<String> = row.iter().map(|n| render_node(n)).collect();
+        let mut starts = Vec::with_capacity(rendered.len());
+        let mut col = 0;
+        for piece in &rendered {
+            starts.push(col);
+            col += visible_len(piece) + 2;
+        }
+        out.push_str("  ");
+        out.push_str(&rendered.join("  "));
+        out.push('\n');
+
+        if r + 1 < rows.len() {
+            let mut connector = vec![' '; col];
+            for (edge, idx) in graph.edges.iter().filter_map(|e| {
+                row.iter()
+                    .position(|n| n.id == e.from)
+                    .map(|idx| (e, idx))
+            }) {
+                let to_rank = ranks[&edge.to];
+                if to_rank == r + 1 {
+                    if let Some(slot) = connector.get_mut(starts[idx]) {
+                        *slot = '|';
+                    }
+                } else if to_rank > r {
+                    spillover.push(edge);
+                }
+            }
+            let line: String = connector.into_iter().collect();
+            if line.trim().is_empty() {
+                continue;
+            }
+            out.push_str("  ");
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+    }
+
+    // Edges a rank-by-rank grid can't draw: same-rank, reversed, or ones
+    // already queued above because they skip a rank.
+    let mut extra: Vec<&Edge> = spillover;
+    for edge in &graph.edges {
+        if ranks[&edge.to] <= ranks[&edge.from] {
+            extra.push(edge);
+        }
+    }
+    if !extra.is_empty() {
+        out.push_str("\nedges:\n");
+        for edge in extra {
+            out.push_str(&format!("  {} {} {}", edge.from, arrow, edge.to));
+            if let Some(label) = &edge.attrs.label {
+                out.push_str(&format!("  [{}]", label));
+            }
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+/// Rank each node by longest path from a source (a node with no incoming
+/// edges) via Kahn's algorithm. A node on or behind a cycle never has its
+/// in-degree reach zero, so it is never queued: one that an edge from a
+/// queued node reached keeps the rank that edge gave it, and the rest are
+/// placed one rank past the highest rank assigned rather than left out.
+fn assign_ranks(graph: &Graph) -> std::collections::HashMap<String, usize> {
+    use std::collections::HashMap;
+
+    let mut remaining: HashMap<String, usize> =
+        graph.nodes.iter().map(|n| (n.id.clone(), 0)).collect();
+    for edge in &graph.edges {
+        *remaining.entry(edge.to.clone()).or_insert(0) += 1;
+    }
+
+    let mut rank: HashMap<String, usize> = HashMap::new();
+    let mut queue: Vec<String> = graph
+        .nodes
+        .iter()
+        .map(|n| n.id.clone())
+        .filter(|id| remaining[id] == 0)
+        .collect();
+    for id in &queue {
+        rank.insert(id.clone(), 0);
+    }
+
+    let mut i = 0;
+    while i < queue.len() {
+        let id = queue[i].clone();
+        i += 1;
+        let r = rank[&id];
+        for edge in graph.edges.iter().filter(|e| e.from == id) {
+            let slot = remaining.get_mut(&edge.to).unwrap();
+            *slot = slot.saturating_sub(1);
+            let candidate = r + 1;
+            let current = rank.get(&edge.to).copied().unwrap_or(0);
+            rank.insert(edge.to.clone(), candidate.max(current));
+            if *slot == 0 && !queue.contains(&edge.to) {
+                queue.push(edge.to.clone());
+            }
+        }
+    }
+
+    // Any node the walk never ranked sits on or behind a cycle; park it one
+    // rank past everything the topological walk did rank rather than
+    // leaving it out of the layout.
+    let fallback_rank = rank.values().copied().max().map(|r| r + 1).unwrap_or(0);
+    for node in &graph.nodes {
+        rank.entry(node.id.clone()).or_insert(fallback_rank);
+    }
+    rank
+}
+
+fn render_node(node: &Node) -> String {
+    let label = node.attrs.label.as_deref().unwrap_or(&node.id);
+    let (open, close) = match node.attrs.shape.as_deref() {
+        Some("box") | Some("rect") | Some("rectangle") | Some("square") => ('[', ']'),
+        Some("diamond") => ('<', '>'),
+        _ => ('(', ')'),
+    };
+    let text = format!("{open}{label}{close}");
+    match ansi_for_color(node.attrs.color.as_deref()) {
+        Some(code) => format!("\x1b[{code}m{text}\x1b[0m"),
+        None => text,
+    }
+}
+
+/// A named ANSI color for the handful of color names a DOT file is likely
+/// to use — not the full X11/SVG color set Graphviz supports.
+fn ansi_for_color(color: Option<&str>) -> Option<u8> {
+    match color?.to_ascii_lowercase().as_str() {
+        "black" => Some(30),
+        "red" => Some(31),
+        "green" => Some(32),
+        "yellow" => Some(33),
+        "blue" => Some(34),
+        "magenta" | "purple" => Some(35),
+        "cyan" => Some(36),
+        "white" | "gray" | "grey" => Some(37),
+        _ => None,
+    }
+}
+
+/// The column width of a rendered node, ignoring any ANSI color codes
+/// wrapped around it.
+fn visible_len(s: &str) -> usize {
+    let mut len = 0;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c == 'm' {
+                    break;
+                }
+            }
+            continue;
+        }
+        len += 1;
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_directed_vs_undirected() {
+        let d = parse("digraph { A -> B; }").unwrap();
+        assert_eq!(d.kind, GraphKind::Directed);
+        let g = parse("graph { A -- B; }").unwrap();
+        assert_eq!(g.kind, GraphKind::Undirected);
+    }
+
+    #[test]
+    fn node_and_edge_statements_are_collected() {
+        let g = parse("digraph { A; B; A -> B; }").unwrap();
+        assert_eq!(g.nodes.len(), 2);
+        assert_eq!(g.edges.len(), 1);
+        assert_eq!(g.edges[0].from, "A");
+        assert_eq!(g.edges[0].to, "B");
+    }
+
+    #[test]
+    fn edge_chain_expands_to_pairwise_edges() {
+        let g = parse("digraph { A -> B -> C; }").unwrap();
+        assert_eq!(g.edges.len(), 2);
+        assert_eq!(g.edges[1].from, "B");
+        assert_eq!(g.edges[1].to, "C");
+    }
+
+    #[test]
+    fn attributes_are_parsed() {
+        let g = parse(r#"digraph { A [label="start", shape=box, color=red]; }"#).unwrap();
+        let a = &g.nodes[0].attrs;
+        assert_eq!(a.label.as_deref(), Some("start"));
+        assert_eq!(a.shape.as_deref(), Some("box"));
+        assert_eq!(a.color.as_deref(), Some("red"));
+    }
+
+    #[test]
+    fn missing_header_is_a_parse_error() {
+        assert!(matches!(parse("A -> B;"), Err(ParseError::MissingHeader)));
+    }
+
+    #[test]
+    fn unterminated_block_is_a_parse_error() {
+        assert!(matches!(parse("digraph { A -> B;"), Err(ParseError::UnterminatedBlock)));
+    }
+
+    #[test]
+    fn rendered_view_lays_out_boxes_and_connectors() {
+        let block = GraphBlock::new("digraph { A -> B; B -> C; }");
+        let out = block.render(View::Rendered);
+        assert!(out.contains("(A)"));
+        assert!(out.contains("(B)"));
+        assert!(out.contains("(C)"));
+        assert!(out.contains('|'));
+    }
+
+    #[test]
+    fn shape_and_color_attributes_affect_rendering() {
+        let block = GraphBlock::new(r#"digraph { A [shape=box, color=red]; }"#);
+        let out = block.render(View::Rendered);
+        assert!(out.contains("[A]"));
+        assert!(out.contains("\x1b[31m"));
+    }
+
+    #[test]
+    fn unparseable_source_falls_back_to_highlighted_source() {
+        let block = GraphBlock::new("not a graph at all");
+        let rendered = block.render(View::Rendered);
+        let source = block.render(View::Source);
+        assert_eq!(rendered, source);
+    }
+
+    #[test]
+    fn source_view_is_always_the_raw_text_highlighted() {
+        let block = GraphBlock::new("digraph { A -> B; }");
+        let out = block.render(View::Source);
+        assert!(out.contains("\x1b[35mdigraph\x1b[0m"));
+    }
+}
@@ -0,0 +1,246 @@
+//! A minimal best-effort syntax highlighter.
+//!
+//! Just enough token classification — keywords, strings, numbers, comments,
+//! macro invocations — to color the Rust fixture, the same "heuristic, not
+//! a resolver" trade-off [`crate::rules`] and [`crate::liveness`] make.
+//! [`highlight_line`] is line-oriented: it doesn't track state across
+//! lines, so a block comment or string literal that spans multiple lines
+//! is only colored correctly within the line it starts and ends on.
+//!
+//! The tokenizer itself is language-agnostic — comments, strings, numbers
+//! and identifiers all look the same shape across the small set of
+//! C-like languages this crate renders — so only the keyword list varies
+//! per `lang`; see [`keywords_for`].
+
+/// A classified run of source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Keyword,
+    String,
+    Number,
+    Comment,
+    Macro,
+    Plain,
+}
+
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "Self", "self", "static", "struct", "super", "trait", "type", "unsafe", "use",
+    "where", "while",
+];
+
+/// DOT only has a handful of reserved words; `node`/`edge`/`subgraph` are
+/// included even though [`crate::graph`] doesn't parse subgraphs, since
+/// they're still reserved and worth coloring as such in the source view.
+const DOT_KEYWORDS: &[&str] = &["strict", "digraph", "graph", "subgraph", "node", "edge"];
+
+/// The keyword list to tokenize `lang` against, or `None` for an
+/// unrecognized (or absent) language, which passes the line through
+/// unchanged.
+fn keywords_for(lang: Option<&str>) -> Option<&'static [&'static str]> {
+    let lang = lang?;
+    if lang.eq_ignore_ascii_case("rust") {
+        Some(RUST_KEYWORDS)
+    } else if lang.eq_ignore_ascii_case("dot") || lang.eq_ignore_ascii_case("graphviz") {
+        Some(DOT_KEYWORDS)
+    } else {
+        None
+    }
+}
+
+/// The ANSI SGR sequence this token kind is drawn in, or `None` for
+/// [`TokenKind::Plain`], which is emitted with no styling at all.
+fn ansi_code(kind: TokenKind) -> Option<&'static str> {
+    match kind {
+        TokenKind::Keyword => Some("\x1b[35m"),
+        TokenKind::String => Some("\x1b[32m"),
+        TokenKind::Number => Some("\x1b[33m"),
+        TokenKind::Comment => Some("\x1b[2m"),
+        TokenKind::Macro => Some("\x1b[36m"),
+        TokenKind::Plain => None,
+    }
+}
+
+/// Highlight one line of `lang` source, returning it with ANSI color codes
+/// inserted. Unrecognized (or absent) languages pass through unchanged —
+/// `"rust"` and `"dot"`/`"graphviz"` are tokenized today.
+pub fn highlight_line(line: &str, lang: Option<&str>) -> String {
+    let Some(keywords) = keywords_for(lang) else {
+        return line.to_string();
+    };
+    let mut out = String::with_capacity(line.len() + 16);
+    for token in tokenize(line, keywords) {
+        match ansi_code(token.kind) {
+            Some(code) => {
+                out.push_str(code);
+                out.push_str(token.text);
+                out.push_str("\x1b[0m");
+            }
+            None => out.push_str(token.text),
+        }
+    }
+    out
+}
+
+fn tokenize<'a>(line: &'a str, keywords: &[&str]) -> Vec<Token<'a>> {
+    let bytes = line.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let mut plain_start = 0;
+
+    macro_rules! flush_plain {
+        ($end:expr) => {
+            if plain_start < $end {
+                tokens.push(Token {
+                    kind: TokenKind::Plain,
+                    text: &line[plain_start..$end],
+                });
+            }
+        };
+    }
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b'/' && bytes.get(i + 1) == Some(&b'/') {
+            flush_plain!(i);
+            tokens.push(Token {
+                kind: TokenKind::Comment,
+                text: &line[i..],
+            });
+            plain_start = line.len();
+            break;
+        }
+        if b == b'/' && bytes.get(i + 1) == Some(&b'*') {
+            flush_plain!(i);
+            let start = i;
+            i = match line[i + 2..].find("*/") {
+                Some(rel) => i + 2 + rel + 2,
+                None => line.len(),
+            };
+            tokens.push(Token {
+                kind: TokenKind::Comment,
+                text: &line[start..i],
+            });
+            plain_start = i;
+            continue;
+        }
+        if b == b'"' {
+            flush_plain!(i);
+            let start = i;
+            i += 1;
+            while i < bytes.len() {
+                if bytes[i] == b'\\' {
+                    i += 2;
+                    continue;
+                }
+                if bytes[i] == b'"' {
+                    i += 1;
+                    break;
+                }
+                i += 1;
+            }
+            tokens.push(Token {
+                kind: TokenKind::String,
+                text: &line[start..i.min(line.len())],
+            });
+            plain_start = i;
+            continue;
+        }
+        if b.is_ascii_digit() {
+            flush_plain!(i);
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || matches!(bytes[i], b'.' | b'_')) {
+                i += 1;
+            }
+            tokens.push(Token {
+                kind: TokenKind::Number,
+                text: &line[start..i],
+            });
+            plain_start = i;
+            continue;
+        }
+        if is_ident_start(b) {
+            flush_plain!(i);
+            let start = i;
+            while i < bytes.len() && is_ident_byte(bytes[i]) {
+                i += 1;
+            }
+            let word = &line[start..i];
+            let kind = if keywords.contains(&word) {
+                TokenKind::Keyword
+            } else if bytes.get(i) == Some(&b'!') {
+                i += 1;
+                TokenKind::Macro
+            } else {
+                TokenKind::Plain
+            };
+            tokens.push(Token {
+                kind,
+                text: &line[start..i],
+            });
+            plain_start = i;
+            continue;
+        }
+        i += 1;
+    }
+    flush_plain!(bytes.len());
+    tokens
+}
+
+fn is_ident_start(b: u8) -> bool {
+    b == b'_' || b.is_ascii_alphabetic()
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b == b'_' || b.is_ascii_alphanumeric()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyword_and_string_are_colored() {
+        let out = highlight_line(r#"let x = "hi";"#, Some("rust"));
+        assert!(out.contains("\x1b[35mlet\x1b[0m"));
+        assert!(out.contains("\x1b[32m\"hi\"\x1b[0m"));
+    }
+
+    #[test]
+    fn macro_invocation_is_colored() {
+        let out = highlight_line(r#"println!("{}", n);"#, Some("rust"));
+        assert!(out.contains("\x1b[36mprintln!\x1b[0m"));
+    }
+
+    #[test]
+    fn line_comment_runs_to_end_of_line() {
+        let out = highlight_line("let x = 1; // note", Some("rust"));
+        assert!(out.contains("\x1b[2m// note\x1b[0m"));
+    }
+
+    #[test]
+    fn unrecognized_language_passes_through_unchanged() {
+        let src = "def f(x): return x";
+        assert_eq!(highlight_line(src, Some("python")), src);
+    }
+
+    #[test]
+    fn dot_keyword_is_colored() {
+        let out = highlight_line("digraph G {", Some("dot"));
+        assert!(out.contains("\x1b[35mdigraph\x1b[0m"));
+        let out = highlight_line("graph G {", Some("graphviz"));
+        assert!(out.contains("\x1b[35mgraph\x1b[0m"));
+    }
+
+    #[test]
+    fn no_language_passes_through_unchanged() {
+        let src = "let x = 1;";
+        assert_eq!(highlight_line(src, None), src);
+    }
+}
@@ -0,0 +1,15 @@
+//! agentviewer — rendering primitives for the code payloads that agents emit.
+//!
+//! The crate turns raw source blocks (the Rust fixture in `testdata/` is the
+//! canonical exercise) into richly annotated terminal output: syntax
+//! highlighting, rustc-style diagnostic spans, lint underlines, diffs, and
+//! laid-out DOT/Graphviz graphs.
+
+pub mod block;
+pub mod code_block;
+pub mod diagnostics;
+pub mod diff;
+pub mod graph;
+pub mod highlight;
+pub mod liveness;
+pub mod rules;
@@ -0,0 +1,470 @@
+//! Dead-store detection for `let` bindings via backward liveness analysis.
+//!
+//! This mirrors the classic dataflow pass: each tracked binding gets an
+//! index, the live set is a [`BitSet`] over those indices, and
+//! [`find_dead_stores`] walks the source's def/use events in reverse
+//! execution order — a use marks its binding live, and a def (the original
+//! `let`, or a later plain reassignment of the same name) that reaches a
+//! point where its binding isn't live is a dead store. Loop bodies are
+//! unrolled once before the sweep so a read at the top of the next
+//! iteration can keep a reassignment at the bottom of this one alive; the
+//! sweep re-runs until the reported dead set stops changing (capped at
+//! [`MAX_FIXPOINT_PASSES`] as a backstop).
+//!
+//! Like [`crate::rules`], this is a token heuristic rather than a resolver:
+//! only plain `let [mut] NAME [: TYPE] = ...;` bindings are tracked
+//! (destructuring patterns are left to a future resolver-backed pass),
+//! `NAME`-prefixed-with-`_` bindings are skipped since that's Rust's own
+//! convention for an intentionally-unused binding, and a reassignment is
+//! recognized only as a bare `NAME = ...;` (compound assignment like `+=`
+//! reads the old value, so it counts as a use, not a store). A loop body is
+//! taken to be the block opening at the first `{` after `for`/`while`/
+//! `loop`, which mistakes a closure literal in a `for`'s iterator
+//! expression for the body; that's rare enough in practice to leave alone.
+//! Braces and semicolons aren't distinguished from ones inside string
+//! literals or comments, so an escaped `{{`/`}}` in a format string can
+//! throw off scope tracking — ordinary balanced `{}` placeholders, the
+//! overwhelming majority, scan through harmlessly.
+
+use crate::diagnostics::Span;
+use std::collections::HashMap;
+
+/// Defensive bound on fixpoint iterations. A single loop nesting level
+/// stabilizes in two passes; this just guards against a pathological input
+/// spinning forever.
+const MAX_FIXPOINT_PASSES: usize = 8;
+
+/// Find every dead store in `source`: a `let` binding or reassignment whose
+/// value is never read before the binding goes out of scope (or is
+/// overwritten again). Returned spans cover just the binding's name, sorted
+/// by position, ready for the renderer to dim.
+pub fn find_dead_stores(source: &str) -> Vec<Span> {
+    let (bindings, events, loop_regions) = scan(source);
+    let expanded = expand_loops(&events, &loop_regions);
+
+    let mut live = BitSet::new(bindings.len());
+    let mut verdicts: HashMap<(usize, usize), (Span, bool)> = HashMap::new();
+    let mut prev_dead: Vec<(usize, usize)> = Vec::new();
+
+    for _ in 0..MAX_FIXPOINT_PASSES {
+        for (idx, span, dead) in sweep(&expanded, &mut live) {
+            verdicts.insert((idx, span.start), (span, dead));
+        }
+        let mut dead: Vec<(usize, usize)> = verdicts
+            .iter()
+            .filter(|(_, (_, dead))| *dead)
+            .map(|(&key, _)| key)
+            .collect();
+        dead.sort_unstable();
+        if dead == prev_dead {
+            break;
+        }
+        prev_dead = dead;
+    }
+
+    let mut spans: Vec<Span> = verdicts
+        .into_values()
+        .filter(|(_, dead)| *dead)
+        .map(|(span, _)| span)
+        .collect();
+    spans.sort_by_key(|s| s.start);
+    spans
+}
+
+/// A tracked `let` binding: its name (for resolving later uses/
+/// reassignments against the nearest shadow) and the byte range in which
+/// it's visible.
+struct BindingInfo {
+    name: String,
+    scope_start: usize,
+    scope_end: usize,
+}
+
+#[derive(Clone, Copy)]
+enum EventKind {
+    Use(usize),
+    Def(usize, Span),
+}
+
+#[derive(Clone, Copy)]
+struct Event {
+    pos: usize,
+    kind: EventKind,
+}
+
+struct Candidate {
+    name: String,
+    span: Span,
+    is_assign: bool,
+}
+
+/// One nested block, tracking which bindings it opened (so their scope can
+/// close when the block does) and whether it's a loop body (so its events
+/// can be unrolled for the fixpoint pass).
+struct ScopeFrame {
+    bindings: Vec<usize>,
+    loop_start: Option<usize>,
+}
+
+/// Tokenize `source` into bindings, liveness events and loop-body spans.
+fn scan(source: &str) -> (Vec<BindingInfo>, Vec<Event>, Vec<Span>) {
+    let bytes = source.as_bytes();
+    let mut bindings: Vec<BindingInfo> = Vec::new();
+    let mut events: Vec<Event> = Vec::new();
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut raw_loop_regions: Vec<Span> = Vec::new();
+    let mut scopes: Vec<ScopeFrame> = vec![ScopeFrame {
+        bindings: Vec::new(),
+        loop_start: None,
+    }];
+
+    let mut awaiting_binder = false;
+    let mut pending_loop_keyword = false;
+    let mut open_let: Option<(usize, usize)> = None;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b'{' {
+            let loop_start = pending_loop_keyword.then_some(i);
+            pending_loop_keyword = false;
+            scopes.push(ScopeFrame {
+                bindings: Vec::new(),
+                loop_start,
+            });
+            i += 1;
+            continue;
+        }
+        if b == b'}' {
+            if scopes.len() > 1 {
+                let frame = scopes.pop().unwrap();
+                for idx in frame.bindings {
+                    bindings[idx].scope_end = i;
+                }
+                if let Some(start) = frame.loop_start {
+                    raw_loop_regions.push(Span::new(start, i + 1));
+                }
+            }
+            i += 1;
+            continue;
+        }
+        if b == b';' {
+            if let Some((idx, depth)) = open_let {
+                if scopes.len() - 1 == depth {
+                    bindings[idx].scope_start = i + 1;
+                    open_let = None;
+                }
+            }
+            i += 1;
+            continue;
+        }
+        if awaiting_binder && matches!(b, b'(' | b'[') {
+            // A tuple (`let (a, b) = ..`) or slice (`let [a, b] = ..`)
+            // pattern starting right after `let`/`mut` with no binder name
+            // of its own — same "leave it to a resolver" scope limit as a
+            // destructuring pattern headed by an identifier.
+            awaiting_binder = false;
+        }
+        if is_ident_start(b) {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && is_ident_byte(bytes[i]) {
+                i += 1;
+            }
+            let name = &source[start..i];
+            match name {
+                "let" => awaiting_binder = true,
+                "mut" if awaiting_binder => {}
+                // Left pending across the header's pattern/condition tokens
+                // (including a `while let` guard) until the next `{`, which
+                // is taken to be the loop body.
+                "for" | "while" | "loop" => pending_loop_keyword = true,
+                "_" => awaiting_binder = false,
+                _ => {
+                    if awaiting_binder {
+                        awaiting_binder = false;
+                        // `_`-prefixed bindings are an explicit opt-out of
+                        // unused-store analysis, same convention rustc uses.
+                        if name.starts_with('_') {
+                            continue;
+                        }
+                        // A capitalized name (`Some`, `Err`, a struct/enum
+                        // path) or one immediately followed by `(`/`{`/`::`
+                        // is a destructuring pattern, e.g. `let Some(n) = ..`
+                        // or `let Point { x, y } = ..` — not a plain
+                        // binder. Those are left to a future
+                        // resolver-backed pass, same as grouped `use`
+                        // imports in `crate::rules`.
+                        if name.starts_with(|c: char| c.is_ascii_uppercase())
+                            || is_destructure_head(bytes, i)
+                        {
+                            continue;
+                        }
+                        let idx = bindings.len();
+                        let depth = scopes.len() - 1;
+                        let span = Span::new(start, i);
+                        bindings.push(BindingInfo {
+                            name: name.to_string(),
+                            scope_start: i,
+                            scope_end: source.len(),
+                        });
+                        scopes.last_mut().unwrap().bindings.push(idx);
+                        events.push(Event {
+                            pos: start,
+                            kind: EventKind::Def(idx, span),
+                        });
+                        open_let = Some((idx, depth));
+                    } else {
+                        let span = Span::new(start, i);
+                        if is_path_segment(bytes, start) {
+                            // `foo.bar` / `Type::bar` — not a reference to a
+                            // local binding named `bar`.
+                        } else if is_assignment_target(bytes, i) {
+                            candidates.push(Candidate {
+                                name: name.to_string(),
+                                span,
+                                is_assign: true,
+                            });
+                        } else {
+                            candidates.push(Candidate {
+                                name: name.to_string(),
+                                span,
+                                is_assign: false,
+                            });
+                        }
+                    }
+                }
+            }
+            continue;
+        }
+        i += 1;
+    }
+
+    // Anything still open at EOF (including the implicit file-level scope)
+    // runs to the end of the source.
+    for frame in scopes.drain(..) {
+        for idx in frame.bindings {
+            bindings[idx].scope_end = source.len();
+        }
+    }
+
+    for candidate in candidates {
+        if let Some(idx) = resolve(&bindings, &candidate.name, candidate.span.start) {
+            let kind = if candidate.is_assign {
+                EventKind::Def(idx, candidate.span)
+            } else {
+                EventKind::Use(idx)
+            };
+            events.push(Event {
+                pos: candidate.span.start,
+                kind,
+            });
+        }
+    }
+    events.sort_by_key(|e| e.pos);
+
+    // Keep only outermost loop regions — an inner loop's back-edge is
+    // already covered once the loop that contains it gets unrolled.
+    raw_loop_regions.sort_by_key(|s| s.start);
+    let mut loop_regions: Vec<Span> = Vec::new();
+    for region in raw_loop_regions {
+        let nested = loop_regions
+            .iter()
+            .any(|outer: &Span| outer.start <= region.start && region.end <= outer.end);
+        if !nested {
+            loop_regions.push(region);
+        }
+    }
+
+    (bindings, events, loop_regions)
+}
+
+/// The most recently declared binding named `name` that's in scope at
+/// `pos` — i.e. the nearest shadow, Rust's own name-resolution rule.
+fn resolve(bindings: &[BindingInfo], name: &str, pos: usize) -> Option<usize> {
+    bindings
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.name == name && b.scope_start <= pos && pos < b.scope_end)
+        .max_by_key(|(_, b)| b.scope_start)
+        .map(|(idx, _)| idx)
+}
+
+/// Unroll each loop body's events once, so a reverse sweep over the second
+/// copy (standing in for "the next iteration") informs the verdict for
+/// the first (the real, reported occurrence).
+fn expand_loops(events: &[Event], loop_regions: &[Span]) -> Vec<Event> {
+    if loop_regions.is_empty() {
+        return events.to_vec();
+    }
+    let mut out = Vec::with_capacity(events.len() * 2);
+    let mut i = 0;
+    while i < events.len() {
+        if let Some(region) = loop_regions
+            .iter()
+            .find(|r| r.start <= events[i].pos && events[i].pos < r.end)
+        {
+            let start = i;
+            while i < events.len() && events[i].pos < region.end {
+                i += 1;
+            }
+            out.extend_from_slice(&events[start..i]);
+            out.extend_from_slice(&events[start..i]);
+        } else {
+            out.push(events[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// One backward pass over `events`, returning a dead/alive verdict for
+/// every def encountered. `live` is not reset by the caller between passes
+/// — carrying it over is what lets a fixpoint re-run converge.
+fn sweep(events: &[Event], live: &mut BitSet) -> Vec<(usize, Span, bool)> {
+    let mut verdicts = Vec::new();
+    for event in events.iter().rev() {
+        match event.kind {
+            EventKind::Use(idx) => live.set(idx),
+            EventKind::Def(idx, span) => {
+                verdicts.push((idx, span, !live.get(idx)));
+                live.clear(idx);
+            }
+        }
+    }
+    verdicts
+}
+
+/// A fixed-size bitset indexed by binding index, backed by `u64` words.
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        BitSet {
+            words: vec![0u64; len.div_ceil(64)],
+        }
+    }
+
+    fn set(&mut self, i: usize) {
+        self.words[i / 64] |= 1u64 << (i % 64);
+    }
+
+    fn clear(&mut self, i: usize) {
+        self.words[i / 64] &= !(1u64 << (i % 64));
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self.words[i / 64] & (1u64 << (i % 64)) != 0
+    }
+}
+
+fn is_ident_start(b: u8) -> bool {
+    b == b'_' || b.is_ascii_alphabetic()
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b == b'_' || b.is_ascii_alphanumeric()
+}
+
+/// Whether the identifier ending at `end` is immediately followed by `(`,
+/// `{` or `::` — the shape of a tuple-struct/enum or path pattern rather
+/// than a plain binder name.
+fn is_destructure_head(bytes: &[u8], end: usize) -> bool {
+    let mut j = end;
+    while j < bytes.len() && matches!(bytes[j], b' ' | b'\t') {
+        j += 1;
+    }
+    matches!(bytes.get(j), Some(&b'(') | Some(&b'{'))
+        || (bytes.get(j) == Some(&b':') && bytes.get(j + 1) == Some(&b':'))
+}
+
+/// Whether the identifier ending just before `start` (i.e. the one we just
+/// scanned) is a field/path access — `foo.bar` or `Type::bar` — rather than
+/// a bare reference to a local.
+fn is_path_segment(bytes: &[u8], start: usize) -> bool {
+    let mut j = start;
+    while j > 0 && matches!(bytes[j - 1], b' ' | b'\t') {
+        j -= 1;
+    }
+    j > 0 && (bytes[j - 1] == b'.' || (j > 1 && bytes[j - 1] == b':' && bytes[j - 2] == b':'))
+}
+
+/// Whether the identifier ending at `end` is the target of a plain `=`
+/// assignment, as opposed to a read, a comparison (`==`), a match arm
+/// (`=>`), or a compound assignment (`+=` and friends, which read the old
+/// value first and so never reach here — skipping whitespace stops at the
+/// operator character, not at the `=`).
+fn is_assignment_target(bytes: &[u8], end: usize) -> bool {
+    let mut j = end;
+    while j < bytes.len() && matches!(bytes[j], b' ' | b'\t') {
+        j += 1;
+    }
+    if bytes.get(j) != Some(&b'=') {
+        return false;
+    }
+    !matches!(bytes.get(j + 1), Some(&b'=') | Some(&b'>'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dead_names<'a>(source: &'a str, dead: &[Span]) -> Vec<&'a str> {
+        let mut names: Vec<&str> = dead.iter().map(|s| &source[s.start..s.end]).collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn unread_binding_is_flagged_dead() {
+        let src = "fn f() {\n    let x = 1;\n    let y = 2;\n    println!(\"{}\", y);\n}\n";
+        let dead = find_dead_stores(src);
+        assert_eq!(dead_names(src, &dead), vec!["x"]);
+    }
+
+    #[test]
+    fn underscore_prefixed_binding_is_exempt() {
+        let src = "fn f() {\n    let _ignored = compute();\n}\n";
+        assert!(find_dead_stores(src).is_empty());
+    }
+
+    #[test]
+    fn shadow_in_inner_block_is_a_distinct_index() {
+        let src = "fn f() {\n    let x = 1;\n    {\n        let x = 2;\n        println!(\"{}\", x);\n    }\n}\n";
+        let dead = find_dead_stores(src);
+        // The outer `x` is never read again once the inner block shadows
+        // it; the inner `x` is read, so only one dead span comes back even
+        // though both bindings share a name.
+        assert_eq!(dead.len(), 1);
+        assert_eq!(&src[dead[0].start..dead[0].end], "x");
+        assert_eq!(dead[0].start, src.find("let x = 1").unwrap() + 4);
+    }
+
+    #[test]
+    fn reassignment_never_read_is_dead() {
+        let src = "fn f() {\n    let mut acc = 0;\n    acc = 1;\n}\n";
+        let dead = find_dead_stores(src);
+        // Both the initializer and the reassignment are dead stores: the
+        // value `acc` ends up holding is never read.
+        assert_eq!(dead.len(), 2);
+    }
+
+    #[test]
+    fn reassignment_read_after_loop_wraps_is_alive() {
+        let src = "fn f() {\n    let mut x = 0;\n    for i in 0..3 {\n        println!(\"{}\", x);\n        x = i;\n    }\n}\n";
+        let dead = find_dead_stores(src);
+        // `x = i` looks dead in a single straight-line pass (nothing reads
+        // it before the loop body ends) but the next iteration's
+        // `println!` does read it, so the fixpoint pass must clear it.
+        assert!(dead.is_empty());
+    }
+
+    #[test]
+    fn compound_assignment_counts_as_a_use() {
+        let src = "fn f() {\n    let mut total = 0;\n    total += 1;\n}\n";
+        let dead = find_dead_stores(src);
+        assert!(dead.is_empty());
+    }
+}
@@ -0,0 +1,397 @@
+//! A pluggable lint engine that runs over a source block and emits
+//! diagnostics the viewer draws as colored underlines.
+//!
+//! Each lint is a [`Rule`] — `Send + Sync` so a [`RuleEngine`] is free to
+//! fan them out across threads — that inspects the source and pushes
+//! [`Diagnostic`]s into a sink. A rule that can repair the code attaches a
+//! [`Fix`]: a set of [`Indel`]s (byte range + replacement) the viewer can
+//! apply to show the fixed code inline as a diff.
+//!
+//! Two built-in rules ship to exercise the pipeline end to end:
+//! [`UnusedUse`] and [`RedundantClone`]. Both are deliberately lightweight,
+//! line/token heuristics rather than a full resolver — enough to light up
+//! the Rust fixture without pulling in a parser.
+
+use crate::diagnostics::{Severity, Span};
+
+/// A single text edit: delete the bytes in `delete` and splice `insert` in
+/// its place. Named after rust-analyzer's "indel" (insert + delete).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Indel {
+    pub delete: Span,
+    pub insert: String,
+}
+
+impl Indel {
+    /// A pure deletion of `span`.
+    pub fn delete(span: Span) -> Self {
+        Indel {
+            delete: span,
+            insert: String::new(),
+        }
+    }
+}
+
+/// A machine-applicable repair attached to a diagnostic.
+#[derive(Debug, Clone)]
+pub struct Fix {
+    pub label: String,
+    pub edits: Vec<Indel>,
+}
+
+/// One finding produced by a rule.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub message: String,
+    pub severity: Severity,
+    pub fix: Option<Fix>,
+}
+
+impl Diagnostic {
+    /// Lower this lint finding into the rendering-side
+    /// [`crate::diagnostics::Diagnostic`] so it can be drawn as an
+    /// annotated underline.
+    pub fn to_annotation(&self) -> crate::diagnostics::Diagnostic {
+        crate::diagnostics::Diagnostic::new(self.severity, self.span, self.message.clone())
+    }
+}
+
+/// A lint. Kept object-safe and `Send + Sync` so rules can be boxed into an
+/// engine and run in parallel.
+pub trait Rule: Send + Sync {
+    /// A stable identifier, e.g. `unused_use`.
+    fn name(&self) -> &'static str;
+
+    /// Inspect `source` and push any findings into `sink`.
+    fn check(&self, source: &str, sink: &mut Vec<Diagnostic>);
+}
+
+/// Holds a set of rules and runs them over a source block.
+pub struct RuleEngine {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl RuleEngine {
+    pub fn new() -> Self {
+        RuleEngine { rules: Vec::new() }
+    }
+
+    /// An engine preloaded with the built-in rules.
+    pub fn with_builtins() -> Self {
+        let mut engine = RuleEngine::new();
+        engine.register(Box::new(UnusedUse));
+        engine.register(Box::new(RedundantClone));
+        engine
+    }
+
+    pub fn register(&mut self, rule: Box<dyn Rule>) {
+        self.rules.push(rule);
+    }
+
+    /// Run every rule over `source`, returning all findings sorted by the
+    /// start of their span so the viewer can lay them out top-to-bottom.
+    pub fn run(&self, source: &str) -> Vec<Diagnostic> {
+        let mut out = Vec::new();
+        for rule in &self.rules {
+            rule.check(source, &mut out);
+        }
+        out.sort_by_key(|d| (d.span.start, d.span.end));
+        out
+    }
+
+    /// Apply every available autofix in `diags` to `source`, returning the
+    /// repaired text. Edits are applied back-to-front so earlier offsets stay
+    /// valid; overlapping edits are skipped rather than corrupting the text.
+    pub fn apply_fixes(source: &str, diags: &[Diagnostic]) -> String {
+        let mut edits: Vec<&Indel> = diags
+            .iter()
+            .filter_map(|d| d.fix.as_ref())
+            .flat_map(|f| f.edits.iter())
+            .collect();
+        edits.sort_by_key(|e| e.delete.start);
+
+        let mut fixed = source.to_string();
+        let mut last_start = fixed.len();
+        // Walk right-to-left so each replace_range leaves untouched offsets
+        // intact; drop any edit that overlaps one we already applied.
+        for edit in edits.into_iter().rev() {
+            if edit.delete.end > last_start {
+                continue;
+            }
+            fixed.replace_range(edit.delete.start..edit.delete.end, &edit.insert);
+            last_start = edit.delete.start;
+        }
+        fixed
+    }
+}
+
+impl Default for RuleEngine {
+    fn default() -> Self {
+        RuleEngine::new()
+    }
+}
+
+/// Flags `use` imports whose bound name — the `as` alias if one is given,
+/// otherwise the final path segment — is never referenced again in the
+/// block, offering to delete the import line.
+pub struct UnusedUse;
+
+impl Rule for UnusedUse {
+    fn name(&self) -> &'static str {
+        "unused_use"
+    }
+
+    fn check(&self, source: &str, sink: &mut Vec<Diagnostic>) {
+        let mut offset = 0;
+        for line in source.split_inclusive('\n') {
+            let trimmed = line.trim_start();
+            let indent = line.len() - trimmed.len();
+            if let Some(rest) = trimmed.strip_prefix("use ") {
+                // Single-name imports only; grouped `use a::{b, c}` is left
+                // to a future resolver-backed rule.
+                let path = rest.trim_end().trim_end_matches(';');
+                if !path.contains('{') {
+                    let name = match path.rsplit_once(" as ") {
+                        Some((_, alias)) => Some(alias.trim()),
+                        None => path.rsplit("::").next().map(str::trim),
+                    };
+                    if let Some(name) = name {
+                        if !name.is_empty() && reference_count(source, name) <= 1 {
+                            let span = Span::new(offset, offset + line.len());
+                            sink.push(Diagnostic {
+                                span: Span::new(offset + indent, offset + line.trim_end().len()),
+                                message: format!("unused import: `{}`", name),
+                                severity: Severity::Warning,
+                                fix: Some(Fix {
+                                    label: "remove unused import".to_string(),
+                                    edits: vec![Indel::delete(span)],
+                                }),
+                            });
+                        }
+                    }
+                }
+            }
+            offset += line.len();
+        }
+    }
+}
+
+/// Flags a `.clone()` call whose receiver is never referenced again after
+/// it, offering to strip the call and let the value move instead. A true
+/// redundant-clone lint needs borrow information; this heuristic catches
+/// only the narrow, safe-to-autofix case — when nothing later in the block
+/// reads the receiver again, cloning it was never necessary, since moving
+/// it does the same job. A clone of a value that's still read afterwards
+/// (the far more common shape) is left alone entirely: it may well be
+/// replaceable by a borrow, but this rule can't prove that, and flagging it
+/// anyway is how the old version corrupted working code by deleting a
+/// clone the rest of the function still depended on (see the regression
+/// test below).
+pub struct RedundantClone;
+
+impl Rule for RedundantClone {
+    fn name(&self) -> &'static str {
+        "redundant_clone"
+    }
+
+    fn check(&self, source: &str, sink: &mut Vec<Diagnostic>) {
+        let needle = ".clone()";
+        let skip = code_mask(source);
+        let mut from = 0;
+        while let Some(rel) = source[from..].find(needle) {
+            let start = from + rel;
+            let end = start + needle.len();
+            if !skip[start] {
+                if let Some(receiver) = receiver_name(source, start) {
+                    if reference_count(&source[end..], receiver) == 0 {
+                        sink.push(Diagnostic {
+                            span: Span::new(start, end),
+                            message: "redundant clone".to_string(),
+                            severity: Severity::Hint,
+                            fix: Some(Fix {
+                                label: "remove `.clone()`".to_string(),
+                                edits: vec![Indel::delete(Span::new(start, end))],
+                            }),
+                        });
+                    }
+                }
+            }
+            from = end;
+        }
+    }
+}
+
+/// The identifier `.clone()` is called on, e.g. `"a"` for `a.clone()` —
+/// found by walking back from `clone_start` (the index of the `.`) while
+/// the bytes are identifier characters. `None` for a receiver that isn't a
+/// bare identifier (a call, an index, a literal, ...), which this heuristic
+/// can't reason about, so those are left unflagged rather than guessed at.
+fn receiver_name(source: &str, clone_start: usize) -> Option<&str> {
+    let bytes = source.as_bytes();
+    let mut i = clone_start;
+    while i > 0 && is_ident_byte(bytes[i - 1]) {
+        i -= 1;
+    }
+    // A field or path receiver (`self.name`, `Type::NAME`) isn't bare either.
+    let after_path = i > 0 && (bytes[i - 1] == b'.' || (i > 1 && &bytes[i - 2..i] == b"::"));
+    if i == clone_start || bytes[i].is_ascii_digit() || after_path {
+        return None;
+    }
+    Some(&source[i..clone_start])
+}
+
+/// A per-byte mask that's `true` wherever `source` is inside a string
+/// literal or a comment — the spans [`RedundantClone`] must not treat as
+/// code, since a bare `.clone()` substring there isn't a call at all.
+/// Another line/token heuristic, not a lexer: it doesn't special-case raw
+/// strings (`r#"..."#`) or escapes beyond a simple backslash skip.
+fn code_mask(source: &str) -> Vec<bool> {
+    let bytes = source.as_bytes();
+    let mut mask = vec![false; bytes.len()];
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                let start = i;
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                mask[start..i].fill(true);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let start = i;
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i = (i + 2).min(bytes.len());
+                mask[start..i].fill(true);
+            }
+            b'"' => {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(bytes.len());
+                mask[start..i].fill(true);
+            }
+            _ => i += 1,
+        }
+    }
+    mask
+}
+
+/// How many times `name` appears as a whole-word identifier in `source`.
+fn reference_count(source: &str, name: &str) -> usize {
+    let bytes = source.as_bytes();
+    let mut count = 0;
+    let mut from = 0;
+    while let Some(rel) = source[from..].find(name) {
+        let start = from + rel;
+        let end = start + name.len();
+        let before = start.checked_sub(1).map(|i| bytes[i]);
+        let after = bytes.get(end).copied();
+        if !before.map(is_ident_byte).unwrap_or(false) && !after.map(is_ident_byte).unwrap_or(false)
+        {
+            count += 1;
+        }
+        from = end;
+    }
+    count
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b == b'_' || b.is_ascii_alphanumeric()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unused_use_is_flagged_and_fixable() {
+        let src = "use std::io::Write;\nfn main() {}\n";
+        let engine = RuleEngine::with_builtins();
+        let diags = engine.run(src);
+        assert!(diags.iter().any(|d| d.message.contains("Write")));
+        let fixed = RuleEngine::apply_fixes(src, &diags);
+        assert!(!fixed.contains("use std::io::Write;"));
+    }
+
+    #[test]
+    fn used_import_is_not_flagged() {
+        let src = "use std::io::Write;\nfn main() { let _: dyn Write; }\n";
+        let diags = RuleEngine::with_builtins().run(src);
+        assert!(!diags.iter().any(|d| d.message.contains("unused import")));
+    }
+
+    #[test]
+    fn redundant_clone_is_removed() {
+        let src = "fn main() { let x = y.clone(); }\n";
+        let diags = RuleEngine::with_builtins().run(src);
+        assert!(diags.iter().any(|d| d.message == "redundant clone"));
+        let fixed = RuleEngine::apply_fixes(src, &diags);
+        assert_eq!(fixed, "fn main() { let x = y; }\n");
+    }
+
+    #[test]
+    fn severity_selects_a_color() {
+        assert_eq!(Severity::Warning.ansi_color(), 33);
+        assert_eq!(Severity::Hint.ansi_color(), 36);
+    }
+
+    #[test]
+    fn used_aliased_import_is_not_flagged() {
+        let src = "use std::io::Write as W;\nfn main() { let w: W; }\n";
+        let diags = RuleEngine::with_builtins().run(src);
+        assert!(!diags.iter().any(|d| d.message.contains("unused import")));
+    }
+
+    #[test]
+    fn unused_aliased_import_is_flagged_by_its_alias() {
+        let src = "use std::io::Write as W;\nfn main() {}\n";
+        let diags = RuleEngine::with_builtins().run(src);
+        assert!(diags.iter().any(|d| d.message.contains("`W`")));
+        let fixed = RuleEngine::apply_fixes(src, &diags);
+        assert!(!fixed.contains("use std::io::Write as W;"));
+    }
+
+    #[test]
+    fn clone_inside_a_string_literal_is_not_flagged() {
+        let src = "fn main() {\n    let s = \"please .clone() me\";\n}\n";
+        let diags = RuleEngine::with_builtins().run(src);
+        assert!(!diags.iter().any(|d| d.message == "redundant clone"));
+        let fixed = RuleEngine::apply_fixes(src, &diags);
+        assert_eq!(fixed, src);
+    }
+
+    #[test]
+    fn clone_inside_a_comment_is_not_flagged() {
+        let src = "fn main() {\n    // call foo.clone() here\n}\n";
+        let diags = RuleEngine::with_builtins().run(src);
+        assert!(!diags.iter().any(|d| d.message == "redundant clone"));
+    }
+
+    #[test]
+    fn clone_whose_receiver_is_still_used_is_not_flagged() {
+        let src =
+            "fn main() {\n    let a = String::from(\"x\");\n    let b = a.clone();\n    println!(\"{} {}\", a, b);\n}\n";
+        let diags = RuleEngine::with_builtins().run(src);
+        assert!(!diags.iter().any(|d| d.message == "redundant clone"));
+        let fixed = RuleEngine::apply_fixes(src, &diags);
+        assert_eq!(fixed, src);
+    }
+
+    #[test]
+    fn catches_real_issues_in_the_rust_fixture() {
+        let src = include_str!("../testdata/code_rust.rs");
+        let diags = RuleEngine::with_builtins().run(src);
+        assert!(diags.iter().any(|d| d.message.contains("unused import")));
+        assert!(diags.iter().any(|d| d.message == "redundant clone"));
+    }
+}
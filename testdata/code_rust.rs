@@ -9,6 +9,8 @@ use std::io::{self, Read, Write};
 use std::marker::PhantomData;
 use std::ops::{Add, Deref, DerefMut};
 use std::pin::Pin;
+use std::borrow::Cow;
+use std::time::Instant as Clock;
 use std::sync::{Arc, Mutex, RwLock};
 use std::task::{Context, Poll};
 
@@ -414,9 +416,15 @@ mod tests {
 
 // Main function
 fn main() {
+    // Timing
+    let started = Clock::now();
+
     // String types
     let static_str: &'static str = "Hello, World!";
     let string = String::from("Hello, World!");
+    let string_copy = string.clone();
+    let owned_once = String::from("consumed");
+    let moved_via_clone = owned_once.clone();
     let formatted = format!("Value: {}", 42);
     let raw_string = r#"This is a raw string with "quotes""#;
     let byte_string = b"Hello bytes";
@@ -479,6 +487,9 @@ fn main() {
     // Print values
     println!("Static: {}", static_str);
     println!("String: {}", string);
+    println!("Copy: {}", string_copy);
+    println!("Consumed: {}", moved_via_clone);
+    println!("Elapsed: {:?}", started.elapsed());
     println!("Formatted: {}", formatted);
     println!("Raw: {}", raw_string);
     println!("Bytes: {:?}", byte_string);